@@ -0,0 +1,552 @@
+use crate::{BLOCK_LEN, IV, KEY_LEN, MSG_SCHEDULE};
+use arrayvec::ArrayVec;
+use core::arch::x86_64::*;
+
+pub const DEGREE: usize = 16;
+
+#[inline(always)]
+unsafe fn loadu(src: *const u8) -> __m512i {
+    // This is an unaligned load, so the pointer cast is allowed.
+    _mm512_loadu_si512(src as *const i32 as *const _)
+}
+
+#[inline(always)]
+unsafe fn add(a: __m512i, b: __m512i) -> __m512i {
+    _mm512_add_epi32(a, b)
+}
+
+#[inline(always)]
+unsafe fn xor(a: __m512i, b: __m512i) -> __m512i {
+    _mm512_xor_si512(a, b)
+}
+
+#[inline(always)]
+unsafe fn set1(x: u32) -> __m512i {
+    _mm512_set1_epi32(x as i32)
+}
+
+#[inline(always)]
+unsafe fn set16(words: &[u32; 16]) -> __m512i {
+    _mm512_loadu_si512(words.as_ptr() as *const i32 as *const _)
+}
+
+// Unlike AVX2, AVX-512F has native 32-bit rotate instructions (vprold /
+// vprord), so there's no need to build rotations out of separate
+// shift-and-or pairs the way sse41.rs and avx2.rs do.
+#[inline(always)]
+unsafe fn rot16(a: __m512i) -> __m512i {
+    _mm512_ror_epi32(a, 16)
+}
+
+#[inline(always)]
+unsafe fn rot12(a: __m512i) -> __m512i {
+    _mm512_ror_epi32(a, 12)
+}
+
+#[inline(always)]
+unsafe fn rot8(a: __m512i) -> __m512i {
+    _mm512_ror_epi32(a, 8)
+}
+
+#[inline(always)]
+unsafe fn rot7(a: __m512i) -> __m512i {
+    _mm512_ror_epi32(a, 7)
+}
+
+#[inline(always)]
+unsafe fn g(
+    v: &mut [__m512i; 16],
+    m: &[__m512i; 16],
+    a: usize,
+    b: usize,
+    c: usize,
+    d: usize,
+    x: usize,
+    y: usize,
+) {
+    v[a] = add(v[a], m[x]);
+    v[a] = add(v[a], v[b]);
+    v[d] = xor(v[d], v[a]);
+    v[d] = rot16(v[d]);
+    v[c] = add(v[c], v[d]);
+    v[b] = xor(v[b], v[c]);
+    v[b] = rot12(v[b]);
+    v[a] = add(v[a], m[y]);
+    v[a] = add(v[a], v[b]);
+    v[d] = xor(v[d], v[a]);
+    v[d] = rot8(v[d]);
+    v[c] = add(v[c], v[d]);
+    v[b] = xor(v[b], v[c]);
+    v[b] = rot7(v[b]);
+}
+
+#[inline(always)]
+unsafe fn round(v: &mut [__m512i; 16], m: &[__m512i; 16], round_idx: usize) {
+    let s = &MSG_SCHEDULE[round_idx];
+    g(v, m, 0, 4, 8, 12, s[0] as usize, s[1] as usize);
+    g(v, m, 1, 5, 9, 13, s[2] as usize, s[3] as usize);
+    g(v, m, 2, 6, 10, 14, s[4] as usize, s[5] as usize);
+    g(v, m, 3, 7, 11, 15, s[6] as usize, s[7] as usize);
+    g(v, m, 0, 5, 10, 15, s[8] as usize, s[9] as usize);
+    g(v, m, 1, 6, 11, 12, s[10] as usize, s[11] as usize);
+    g(v, m, 2, 7, 8, 13, s[12] as usize, s[13] as usize);
+    g(v, m, 3, 4, 9, 14, s[14] as usize, s[15] as usize);
+}
+
+// Transpose 16 vectors of 16 lanes each so that column `i` of the input
+// becomes row `i` of the output, the same operation transpose_vecs()
+// performs for 8 lanes in avx2.rs, just widened to 512 bits with one extra
+// shuffle stage to cross the two extra 128-bit sub-lanes.
+#[inline(always)]
+unsafe fn transpose_vecs(vecs: &mut [__m512i; DEGREE]) {
+    let ab_0 = _mm512_unpacklo_epi32(vecs[0], vecs[1]);
+    let ab_1 = _mm512_unpackhi_epi32(vecs[0], vecs[1]);
+    let cd_0 = _mm512_unpacklo_epi32(vecs[2], vecs[3]);
+    let cd_1 = _mm512_unpackhi_epi32(vecs[2], vecs[3]);
+    let ef_0 = _mm512_unpacklo_epi32(vecs[4], vecs[5]);
+    let ef_1 = _mm512_unpackhi_epi32(vecs[4], vecs[5]);
+    let gh_0 = _mm512_unpacklo_epi32(vecs[6], vecs[7]);
+    let gh_1 = _mm512_unpackhi_epi32(vecs[6], vecs[7]);
+    let ij_0 = _mm512_unpacklo_epi32(vecs[8], vecs[9]);
+    let ij_1 = _mm512_unpackhi_epi32(vecs[8], vecs[9]);
+    let kl_0 = _mm512_unpacklo_epi32(vecs[10], vecs[11]);
+    let kl_1 = _mm512_unpackhi_epi32(vecs[10], vecs[11]);
+    let mn_0 = _mm512_unpacklo_epi32(vecs[12], vecs[13]);
+    let mn_1 = _mm512_unpackhi_epi32(vecs[12], vecs[13]);
+    let op_0 = _mm512_unpacklo_epi32(vecs[14], vecs[15]);
+    let op_1 = _mm512_unpackhi_epi32(vecs[14], vecs[15]);
+
+    let abcd_0 = _mm512_unpacklo_epi64(ab_0, cd_0);
+    let abcd_1 = _mm512_unpackhi_epi64(ab_0, cd_0);
+    let abcd_2 = _mm512_unpacklo_epi64(ab_1, cd_1);
+    let abcd_3 = _mm512_unpackhi_epi64(ab_1, cd_1);
+    let efgh_0 = _mm512_unpacklo_epi64(ef_0, gh_0);
+    let efgh_1 = _mm512_unpackhi_epi64(ef_0, gh_0);
+    let efgh_2 = _mm512_unpacklo_epi64(ef_1, gh_1);
+    let efgh_3 = _mm512_unpackhi_epi64(ef_1, gh_1);
+    let ijkl_0 = _mm512_unpacklo_epi64(ij_0, kl_0);
+    let ijkl_1 = _mm512_unpackhi_epi64(ij_0, kl_0);
+    let ijkl_2 = _mm512_unpacklo_epi64(ij_1, kl_1);
+    let ijkl_3 = _mm512_unpackhi_epi64(ij_1, kl_1);
+    let mnop_0 = _mm512_unpacklo_epi64(mn_0, op_0);
+    let mnop_1 = _mm512_unpackhi_epi64(mn_0, op_0);
+    let mnop_2 = _mm512_unpacklo_epi64(mn_1, op_1);
+    let mnop_3 = _mm512_unpackhi_epi64(mn_1, op_1);
+
+    macro_rules! shuffle128 {
+        ($a:expr, $b:expr, $imm:expr) => {
+            _mm512_shuffle_i32x4($a, $b, $imm)
+        };
+    }
+    let abcdefgh_0 = shuffle128!(abcd_0, efgh_0, 0x88);
+    let abcdefgh_1 = shuffle128!(abcd_1, efgh_1, 0x88);
+    let abcdefgh_2 = shuffle128!(abcd_2, efgh_2, 0x88);
+    let abcdefgh_3 = shuffle128!(abcd_3, efgh_3, 0x88);
+    let abcdefgh_4 = shuffle128!(abcd_0, efgh_0, 0xdd);
+    let abcdefgh_5 = shuffle128!(abcd_1, efgh_1, 0xdd);
+    let abcdefgh_6 = shuffle128!(abcd_2, efgh_2, 0xdd);
+    let abcdefgh_7 = shuffle128!(abcd_3, efgh_3, 0xdd);
+    let ijklmnop_0 = shuffle128!(ijkl_0, mnop_0, 0x88);
+    let ijklmnop_1 = shuffle128!(ijkl_1, mnop_1, 0x88);
+    let ijklmnop_2 = shuffle128!(ijkl_2, mnop_2, 0x88);
+    let ijklmnop_3 = shuffle128!(ijkl_3, mnop_3, 0x88);
+    let ijklmnop_4 = shuffle128!(ijkl_0, mnop_0, 0xdd);
+    let ijklmnop_5 = shuffle128!(ijkl_1, mnop_1, 0xdd);
+    let ijklmnop_6 = shuffle128!(ijkl_2, mnop_2, 0xdd);
+    let ijklmnop_7 = shuffle128!(ijkl_3, mnop_3, 0xdd);
+
+    vecs[0] = shuffle128!(abcdefgh_0, ijklmnop_0, 0x88);
+    vecs[1] = shuffle128!(abcdefgh_1, ijklmnop_1, 0x88);
+    vecs[2] = shuffle128!(abcdefgh_2, ijklmnop_2, 0x88);
+    vecs[3] = shuffle128!(abcdefgh_3, ijklmnop_3, 0x88);
+    vecs[4] = shuffle128!(abcdefgh_4, ijklmnop_4, 0x88);
+    vecs[5] = shuffle128!(abcdefgh_5, ijklmnop_5, 0x88);
+    vecs[6] = shuffle128!(abcdefgh_6, ijklmnop_6, 0x88);
+    vecs[7] = shuffle128!(abcdefgh_7, ijklmnop_7, 0x88);
+    vecs[8] = shuffle128!(abcdefgh_0, ijklmnop_0, 0xdd);
+    vecs[9] = shuffle128!(abcdefgh_1, ijklmnop_1, 0xdd);
+    vecs[10] = shuffle128!(abcdefgh_2, ijklmnop_2, 0xdd);
+    vecs[11] = shuffle128!(abcdefgh_3, ijklmnop_3, 0xdd);
+    vecs[12] = shuffle128!(abcdefgh_4, ijklmnop_4, 0xdd);
+    vecs[13] = shuffle128!(abcdefgh_5, ijklmnop_5, 0xdd);
+    vecs[14] = shuffle128!(abcdefgh_6, ijklmnop_6, 0xdd);
+    vecs[15] = shuffle128!(abcdefgh_7, ijklmnop_7, 0xdd);
+}
+
+#[inline(always)]
+unsafe fn transpose_msg_vecs(inputs: &[*const u8; DEGREE], block_offset: usize) -> [__m512i; 16] {
+    let mut vecs = [
+        loadu(inputs[0].add(block_offset)),
+        loadu(inputs[1].add(block_offset)),
+        loadu(inputs[2].add(block_offset)),
+        loadu(inputs[3].add(block_offset)),
+        loadu(inputs[4].add(block_offset)),
+        loadu(inputs[5].add(block_offset)),
+        loadu(inputs[6].add(block_offset)),
+        loadu(inputs[7].add(block_offset)),
+        loadu(inputs[8].add(block_offset)),
+        loadu(inputs[9].add(block_offset)),
+        loadu(inputs[10].add(block_offset)),
+        loadu(inputs[11].add(block_offset)),
+        loadu(inputs[12].add(block_offset)),
+        loadu(inputs[13].add(block_offset)),
+        loadu(inputs[14].add(block_offset)),
+        loadu(inputs[15].add(block_offset)),
+    ];
+    // Each vector above holds one 64-byte message block from a different
+    // chunk. Transposing turns that into 16 vectors of word-N-across-all-
+    // chunks, the layout the round function needs.
+    transpose_vecs(&mut vecs);
+    vecs
+}
+
+// A single compress() call has no chunks to parallelize across, so unlike
+// hash16 below, the 16 state words are packed 4-to-a-register as "rows"
+// (row0 = words 0-3, row1 = words 4-7, row2 = words 8-11, row3 = words
+// 12-15) and the G function runs on all 4 columns (then all 4 diagonals)
+// of a row at once. Each row only occupies the low 128 bits of a zmm
+// register, so every op below is done through the AVX-512F masked forms
+// with a 4-bit mask, leaving the upper 384 bits zeroed rather than
+// widening the state out to a real 512-bit layout.
+const ROW_MASK: __mmask16 = 0xf;
+
+#[inline(always)]
+unsafe fn row4(words: [u32; 4]) -> __m512i {
+    let mut buf = [0u32; 16];
+    buf[..4].copy_from_slice(&words);
+    set16(&buf)
+}
+
+#[inline(always)]
+unsafe fn row_to_array(a: __m512i) -> [u32; 4] {
+    let mut out = [0u32; 4];
+    _mm_storeu_si128(
+        out.as_mut_ptr() as *mut __m128i,
+        _mm512_castsi512_si128(a),
+    );
+    out
+}
+
+#[inline(always)]
+unsafe fn row_add(a: __m512i, b: __m512i) -> __m512i {
+    _mm512_maskz_add_epi32(ROW_MASK, a, b)
+}
+
+#[inline(always)]
+unsafe fn row_xor(a: __m512i, b: __m512i) -> __m512i {
+    _mm512_maskz_xor_epi32(ROW_MASK, a, b)
+}
+
+#[inline(always)]
+unsafe fn row_rot16(a: __m512i) -> __m512i {
+    _mm512_maskz_ror_epi32(ROW_MASK, a, 16)
+}
+
+#[inline(always)]
+unsafe fn row_rot12(a: __m512i) -> __m512i {
+    _mm512_maskz_ror_epi32(ROW_MASK, a, 12)
+}
+
+#[inline(always)]
+unsafe fn row_rot8(a: __m512i) -> __m512i {
+    _mm512_maskz_ror_epi32(ROW_MASK, a, 8)
+}
+
+#[inline(always)]
+unsafe fn row_rot7(a: __m512i) -> __m512i {
+    _mm512_maskz_ror_epi32(ROW_MASK, a, 7)
+}
+
+// Rotating row1/row2/row3 by 1/2/3 lanes is the "diagonalize" step that
+// turns the 4 column quarter-rounds into the 4 diagonal quarter-rounds;
+// rotating back by 3/2/1 afterwards is "undiagonalize".
+#[inline(always)]
+unsafe fn row_rotate_left1(a: __m512i) -> __m512i {
+    _mm512_maskz_shuffle_epi32(ROW_MASK, a, 0x39)
+}
+
+#[inline(always)]
+unsafe fn row_rotate_left2(a: __m512i) -> __m512i {
+    _mm512_maskz_shuffle_epi32(ROW_MASK, a, 0x4e)
+}
+
+#[inline(always)]
+unsafe fn row_rotate_left3(a: __m512i) -> __m512i {
+    _mm512_maskz_shuffle_epi32(ROW_MASK, a, 0x93)
+}
+
+#[inline(always)]
+unsafe fn words_to_bytes(words: [u32; 4]) -> [u8; 16] {
+    let mut bytes = [0u8; 16];
+    for (chunk, word) in bytes.chunks_exact_mut(4).zip(words.iter()) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    bytes
+}
+
+#[target_feature(enable = "avx512f", enable = "avx512vl")]
+pub unsafe fn compress(
+    cv: &[u8; 32],
+    block: &[u8; BLOCK_LEN],
+    block_len: u8,
+    offset: u64,
+    flags: u8,
+) -> [u8; 64] {
+    let mut cv_words = [0u32; 8];
+    for (word, bytes) in cv_words.iter_mut().zip(cv.chunks_exact(4)) {
+        *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    }
+    let mut block_words = [0u32; 16];
+    for (word, bytes) in block_words.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    }
+
+    let cv_lo = row4([cv_words[0], cv_words[1], cv_words[2], cv_words[3]]);
+    let cv_hi = row4([cv_words[4], cv_words[5], cv_words[6], cv_words[7]]);
+    let mut row0 = cv_lo;
+    let mut row1 = cv_hi;
+    let mut row2 = row4([IV[0], IV[1], IV[2], IV[3]]);
+    let mut row3 = row4([
+        offset as u32,
+        (offset >> 32) as u32,
+        block_len as u32,
+        flags as u32,
+    ]);
+
+    for round_idx in 0..7 {
+        let s = &MSG_SCHEDULE[round_idx];
+        let m_even0 = row4([
+            block_words[s[0] as usize],
+            block_words[s[2] as usize],
+            block_words[s[4] as usize],
+            block_words[s[6] as usize],
+        ]);
+        let m_odd0 = row4([
+            block_words[s[1] as usize],
+            block_words[s[3] as usize],
+            block_words[s[5] as usize],
+            block_words[s[7] as usize],
+        ]);
+        let m_even1 = row4([
+            block_words[s[8] as usize],
+            block_words[s[10] as usize],
+            block_words[s[12] as usize],
+            block_words[s[14] as usize],
+        ]);
+        let m_odd1 = row4([
+            block_words[s[9] as usize],
+            block_words[s[11] as usize],
+            block_words[s[13] as usize],
+            block_words[s[15] as usize],
+        ]);
+
+        // Column step: all 4 column quarter-rounds at once, one per lane.
+        row0 = row_add(row0, row1);
+        row0 = row_add(row0, m_even0);
+        row3 = row_xor(row3, row0);
+        row3 = row_rot16(row3);
+        row2 = row_add(row2, row3);
+        row1 = row_xor(row1, row2);
+        row1 = row_rot12(row1);
+        row0 = row_add(row0, row1);
+        row0 = row_add(row0, m_odd0);
+        row3 = row_xor(row3, row0);
+        row3 = row_rot8(row3);
+        row2 = row_add(row2, row3);
+        row1 = row_xor(row1, row2);
+        row1 = row_rot7(row1);
+
+        row1 = row_rotate_left1(row1);
+        row2 = row_rotate_left2(row2);
+        row3 = row_rotate_left3(row3);
+
+        // Diagonal step: all 4 diagonal quarter-rounds at once.
+        row0 = row_add(row0, row1);
+        row0 = row_add(row0, m_even1);
+        row3 = row_xor(row3, row0);
+        row3 = row_rot16(row3);
+        row2 = row_add(row2, row3);
+        row1 = row_xor(row1, row2);
+        row1 = row_rot12(row1);
+        row0 = row_add(row0, row1);
+        row0 = row_add(row0, m_odd1);
+        row3 = row_xor(row3, row0);
+        row3 = row_rot8(row3);
+        row2 = row_add(row2, row3);
+        row1 = row_xor(row1, row2);
+        row1 = row_rot7(row1);
+
+        row1 = row_rotate_left3(row1);
+        row2 = row_rotate_left2(row2);
+        row3 = row_rotate_left1(row3);
+    }
+
+    let low0 = row_to_array(row_xor(row0, row2));
+    let low1 = row_to_array(row_xor(row1, row3));
+    let high0 = row_to_array(row_xor(row2, cv_lo));
+    let high1 = row_to_array(row_xor(row3, cv_hi));
+
+    let mut out = [0u8; 64];
+    out[0..16].copy_from_slice(&words_to_bytes(low0));
+    out[16..32].copy_from_slice(&words_to_bytes(low1));
+    out[32..48].copy_from_slice(&words_to_bytes(high0));
+    out[48..64].copy_from_slice(&words_to_bytes(high1));
+    out
+}
+
+#[target_feature(enable = "avx512f", enable = "avx512vl")]
+pub unsafe fn hash16(
+    inputs: &[*const u8; DEGREE],
+    key_words: &[u32; 8],
+    offset: u64,
+    offset_deltas: &[u64; 16],
+    flags: u8,
+    flags_start: u8,
+    flags_end: u8,
+    out: &mut [u8; DEGREE * 32],
+) {
+    let mut h_vecs = [
+        set1(key_words[0]),
+        set1(key_words[1]),
+        set1(key_words[2]),
+        set1(key_words[3]),
+        set1(key_words[4]),
+        set1(key_words[5]),
+        set1(key_words[6]),
+        set1(key_words[7]),
+    ];
+
+    let mut counter_low = [0u32; 16];
+    let mut counter_high = [0u32; 16];
+    for i in 0..DEGREE {
+        let chunk_offset = offset + offset_deltas[i];
+        counter_low[i] = chunk_offset as u32;
+        counter_high[i] = (chunk_offset >> 32) as u32;
+    }
+    let counter_low = set16(&counter_low);
+    let counter_high = set16(&counter_high);
+
+    let blocks = crate::CHUNK_LEN / BLOCK_LEN;
+    let mut block_flags = flags | flags_start;
+    for block in 0..blocks {
+        if block + 1 == blocks {
+            block_flags |= flags_end;
+        }
+        let block_len_vec = set1(BLOCK_LEN as u32);
+        let block_flags_vec = set1(block_flags as u32);
+        let msg_vecs = transpose_msg_vecs(inputs, block * BLOCK_LEN);
+
+        let mut v = [
+            h_vecs[0],
+            h_vecs[1],
+            h_vecs[2],
+            h_vecs[3],
+            h_vecs[4],
+            h_vecs[5],
+            h_vecs[6],
+            h_vecs[7],
+            set1(IV[0]),
+            set1(IV[1]),
+            set1(IV[2]),
+            set1(IV[3]),
+            counter_low,
+            counter_high,
+            block_len_vec,
+            block_flags_vec,
+        ];
+
+        for r in 0..7 {
+            round(&mut v, &msg_vecs, r);
+        }
+
+        h_vecs[0] = xor(v[0], v[8]);
+        h_vecs[1] = xor(v[1], v[9]);
+        h_vecs[2] = xor(v[2], v[10]);
+        h_vecs[3] = xor(v[3], v[11]);
+        h_vecs[4] = xor(v[4], v[12]);
+        h_vecs[5] = xor(v[5], v[13]);
+        h_vecs[6] = xor(v[6], v[14]);
+        h_vecs[7] = xor(v[7], v[15]);
+
+        block_flags = flags;
+    }
+
+    let zero = _mm512_setzero_si512();
+    let mut transposed = [
+        h_vecs[0], h_vecs[1], h_vecs[2], h_vecs[3], h_vecs[4], h_vecs[5], h_vecs[6], h_vecs[7],
+        zero, zero, zero, zero, zero, zero, zero, zero,
+    ];
+    transpose_vecs(&mut transposed);
+    // Each transposed vector holds one chunk's 8-word CV in its low 256
+    // bits and zeros (from the padding above) in its high 256 bits, so only
+    // the low half is real output; store 16 contiguous 32-byte CVs, the
+    // same layout every other backend's hash_many produces.
+    for i in 0..DEGREE {
+        _mm256_storeu_si256(
+            out.as_mut_ptr().add(i * 32) as *mut __m256i,
+            _mm512_castsi512_si256(transposed[i]),
+        );
+    }
+}
+
+#[target_feature(enable = "avx512f", enable = "avx512vl")]
+pub unsafe fn hash_many<A: arrayvec::Array<Item = u8>>(
+    mut inputs: &[&A],
+    key: &[u8; KEY_LEN],
+    mut offset: u64,
+    offset_deltas: &[u64; 16],
+    flags: u8,
+    flags_start: u8,
+    flags_end: u8,
+    mut out: &mut [u8],
+) {
+    let mut key_words = [0u32; 8];
+    for (word, bytes) in key_words.iter_mut().zip(key.chunks_exact(4)) {
+        *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    }
+
+    while inputs.len() >= DEGREE {
+        let mut fixed_size_inputs: ArrayVec<[*const u8; DEGREE]> = ArrayVec::new();
+        for input in &inputs[..DEGREE] {
+            fixed_size_inputs.push(input.as_ptr());
+        }
+        let fixed_size_inputs = fixed_size_inputs.into_inner().unwrap();
+        let out_block = array_mut_ref16(out);
+        hash16(
+            &fixed_size_inputs,
+            &key_words,
+            offset,
+            offset_deltas,
+            flags,
+            flags_start,
+            flags_end,
+            out_block,
+        );
+        // offset_deltas holds the per-chunk offset within the current group;
+        // chunks are contiguous, so the stride between groups is just that
+        // spacing multiplied by how many chunks we consumed.
+        let stride = offset_deltas[1].wrapping_sub(offset_deltas[0]);
+        offset += stride.wrapping_mul(DEGREE as u64);
+        inputs = &inputs[DEGREE..];
+        out = &mut out[DEGREE * 32..];
+    }
+    // Bottom out through AVX2, which in turn bottoms out through SSE4.1 and
+    // portable, the same chain AVX2's own hash_many uses for its remainder
+    // below 8 inputs.
+    crate::avx2::hash_many(
+        inputs,
+        key,
+        offset,
+        offset_deltas,
+        flags,
+        flags_start,
+        flags_end,
+        out,
+    );
+}
+
+#[inline(always)]
+fn array_mut_ref16(out: &mut [u8]) -> &mut [u8; DEGREE * 32] {
+    debug_assert!(out.len() >= DEGREE * 32);
+    unsafe { &mut *(out.as_mut_ptr() as *mut [u8; DEGREE * 32]) }
+}
@@ -0,0 +1,434 @@
+use crate::{BLOCK_LEN, IV, KEY_LEN, MSG_SCHEDULE};
+use arrayvec::ArrayVec;
+use core::arch::aarch64::*;
+
+pub const DEGREE: usize = 4;
+
+#[inline(always)]
+unsafe fn loadu(src: *const u8) -> uint32x4_t {
+    vld1q_u32(src as *const u32)
+}
+
+#[inline(always)]
+unsafe fn storeu(src: uint32x4_t, dest: *mut u8) {
+    vst1q_u32(dest as *mut u32, src)
+}
+
+#[inline(always)]
+unsafe fn add(a: uint32x4_t, b: uint32x4_t) -> uint32x4_t {
+    vaddq_u32(a, b)
+}
+
+#[inline(always)]
+unsafe fn xor(a: uint32x4_t, b: uint32x4_t) -> uint32x4_t {
+    veorq_u32(a, b)
+}
+
+#[inline(always)]
+unsafe fn set1(x: u32) -> uint32x4_t {
+    vdupq_n_u32(x)
+}
+
+#[inline(always)]
+unsafe fn set4(a: u32, b: u32, c: u32, d: u32) -> uint32x4_t {
+    let words = [a, b, c, d];
+    vld1q_u32(words.as_ptr())
+}
+
+// NEON has no single-instruction general rotate, so build each rotation out
+// of a pair of shifts the same way sse41.rs does for SSE4.1, except rot16
+// gets a cheap 16-bit halfword reverse (vrev32q_u16) instead of a shift pair.
+#[inline(always)]
+unsafe fn rot16(a: uint32x4_t) -> uint32x4_t {
+    vreinterpretq_u32_u16(vrev32q_u16(vreinterpretq_u16_u32(a)))
+}
+
+#[inline(always)]
+unsafe fn rot12(a: uint32x4_t) -> uint32x4_t {
+    xor(vshrq_n_u32(a, 12), vshlq_n_u32(a, 20))
+}
+
+#[inline(always)]
+unsafe fn rot8(a: uint32x4_t) -> uint32x4_t {
+    xor(vshrq_n_u32(a, 8), vshlq_n_u32(a, 24))
+}
+
+#[inline(always)]
+unsafe fn rot7(a: uint32x4_t) -> uint32x4_t {
+    xor(vshrq_n_u32(a, 7), vshlq_n_u32(a, 25))
+}
+
+#[inline(always)]
+unsafe fn g(
+    v: &mut [uint32x4_t; 16],
+    m: &[uint32x4_t; 16],
+    a: usize,
+    b: usize,
+    c: usize,
+    d: usize,
+    x: usize,
+    y: usize,
+) {
+    v[a] = add(v[a], m[x]);
+    v[a] = add(v[a], v[b]);
+    v[d] = xor(v[d], v[a]);
+    v[d] = rot16(v[d]);
+    v[c] = add(v[c], v[d]);
+    v[b] = xor(v[b], v[c]);
+    v[b] = rot12(v[b]);
+    v[a] = add(v[a], m[y]);
+    v[a] = add(v[a], v[b]);
+    v[d] = xor(v[d], v[a]);
+    v[d] = rot8(v[d]);
+    v[c] = add(v[c], v[d]);
+    v[b] = xor(v[b], v[c]);
+    v[b] = rot7(v[b]);
+}
+
+#[inline(always)]
+unsafe fn round(v: &mut [uint32x4_t; 16], m: &[uint32x4_t; 16], round_idx: usize) {
+    let s = &MSG_SCHEDULE[round_idx];
+    g(v, m, 0, 4, 8, 12, s[0] as usize, s[1] as usize);
+    g(v, m, 1, 5, 9, 13, s[2] as usize, s[3] as usize);
+    g(v, m, 2, 6, 10, 14, s[4] as usize, s[5] as usize);
+    g(v, m, 3, 7, 11, 15, s[6] as usize, s[7] as usize);
+    g(v, m, 0, 5, 10, 15, s[8] as usize, s[9] as usize);
+    g(v, m, 1, 6, 11, 12, s[10] as usize, s[11] as usize);
+    g(v, m, 2, 7, 8, 13, s[12] as usize, s[13] as usize);
+    g(v, m, 3, 4, 9, 14, s[14] as usize, s[15] as usize);
+}
+
+// Transpose the 4 state vectors (one per chunk) the same way sse41.rs does
+// for its own degree-4 group, using NEON vtrnq plus recombining low/high
+// halves instead of _mm_unpacklo/hi and _mm_shuffle.
+#[inline(always)]
+unsafe fn transpose_vecs(vecs: &mut [uint32x4_t; DEGREE]) {
+    let ab = vtrnq_u32(vecs[0], vecs[1]);
+    let cd = vtrnq_u32(vecs[2], vecs[3]);
+    vecs[0] = vcombine_u32(vget_low_u32(ab.0), vget_low_u32(cd.0));
+    vecs[1] = vcombine_u32(vget_low_u32(ab.1), vget_low_u32(cd.1));
+    vecs[2] = vcombine_u32(vget_high_u32(ab.0), vget_high_u32(cd.0));
+    vecs[3] = vcombine_u32(vget_high_u32(ab.1), vget_high_u32(cd.1));
+}
+
+#[inline(always)]
+unsafe fn transpose_msg_vecs(inputs: &[*const u8; DEGREE], block_offset: usize) -> [uint32x4_t; 16] {
+    let mut out = [set1(0); 16];
+    // Each chunk's 64-byte block is 4 sub-blocks of 4 words each. Load one
+    // sub-block per chunk (16 loads total), then transpose each group of
+    // 4 to turn "chunk c's words [4i..4i+4)" into 4 vectors of
+    // "word 4i+j across all chunks", exactly like sse41 does for its own
+    // degree-4 message transpose.
+    for sub_block in 0..4 {
+        let sub_offset = block_offset + sub_block * 16;
+        let mut vecs = [
+            loadu(inputs[0].add(sub_offset)),
+            loadu(inputs[1].add(sub_offset)),
+            loadu(inputs[2].add(sub_offset)),
+            loadu(inputs[3].add(sub_offset)),
+        ];
+        transpose_vecs(&mut vecs);
+        out[sub_block * 4] = vecs[0];
+        out[sub_block * 4 + 1] = vecs[1];
+        out[sub_block * 4 + 2] = vecs[2];
+        out[sub_block * 4 + 3] = vecs[3];
+    }
+    out
+}
+
+// A single compress() call has no chunks to parallelize across, so unlike
+// hash4 below, the 16 state words are packed 4-to-a-register as "rows"
+// (row0 = words 0-3, row1 = words 4-7, row2 = words 8-11, row3 = words
+// 12-15) and the G function runs on all 4 columns (then all 4 diagonals)
+// of a row at once, the same technique sse41.rs's own compress() uses for
+// its own 128-bit registers.
+#[inline(always)]
+unsafe fn row_rotate_left1(a: uint32x4_t) -> uint32x4_t {
+    vextq_u32(a, a, 1)
+}
+
+#[inline(always)]
+unsafe fn row_rotate_left2(a: uint32x4_t) -> uint32x4_t {
+    vextq_u32(a, a, 2)
+}
+
+#[inline(always)]
+unsafe fn row_rotate_left3(a: uint32x4_t) -> uint32x4_t {
+    vextq_u32(a, a, 3)
+}
+
+#[inline(always)]
+unsafe fn words_to_bytes(words: [u32; 4]) -> [u8; 16] {
+    let mut bytes = [0u8; 16];
+    for (chunk, word) in bytes.chunks_exact_mut(4).zip(words.iter()) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    bytes
+}
+
+#[inline(always)]
+unsafe fn row_to_array(a: uint32x4_t) -> [u32; 4] {
+    let mut out = [0u32; 4];
+    storeu(a, out.as_mut_ptr() as *mut u8);
+    out
+}
+
+#[target_feature(enable = "neon")]
+pub unsafe fn compress(
+    cv: &[u8; 32],
+    block: &[u8; BLOCK_LEN],
+    block_len: u8,
+    offset: u64,
+    flags: u8,
+) -> [u8; 64] {
+    let mut cv_words = [0u32; 8];
+    for (word, bytes) in cv_words.iter_mut().zip(cv.chunks_exact(4)) {
+        *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    }
+    let mut block_words = [0u32; 16];
+    for (word, bytes) in block_words.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    }
+
+    let cv_lo = set4(cv_words[0], cv_words[1], cv_words[2], cv_words[3]);
+    let cv_hi = set4(cv_words[4], cv_words[5], cv_words[6], cv_words[7]);
+    let mut row0 = cv_lo;
+    let mut row1 = cv_hi;
+    let mut row2 = set4(IV[0], IV[1], IV[2], IV[3]);
+    let mut row3 = set4(
+        offset as u32,
+        (offset >> 32) as u32,
+        block_len as u32,
+        flags as u32,
+    );
+
+    for round_idx in 0..7 {
+        let s = &MSG_SCHEDULE[round_idx];
+        let m_even0 = set4(
+            block_words[s[0] as usize],
+            block_words[s[2] as usize],
+            block_words[s[4] as usize],
+            block_words[s[6] as usize],
+        );
+        let m_odd0 = set4(
+            block_words[s[1] as usize],
+            block_words[s[3] as usize],
+            block_words[s[5] as usize],
+            block_words[s[7] as usize],
+        );
+        let m_even1 = set4(
+            block_words[s[8] as usize],
+            block_words[s[10] as usize],
+            block_words[s[12] as usize],
+            block_words[s[14] as usize],
+        );
+        let m_odd1 = set4(
+            block_words[s[9] as usize],
+            block_words[s[11] as usize],
+            block_words[s[13] as usize],
+            block_words[s[15] as usize],
+        );
+
+        // Column step: all 4 column quarter-rounds at once, one per lane.
+        row0 = add(row0, row1);
+        row0 = add(row0, m_even0);
+        row3 = xor(row3, row0);
+        row3 = rot16(row3);
+        row2 = add(row2, row3);
+        row1 = xor(row1, row2);
+        row1 = rot12(row1);
+        row0 = add(row0, row1);
+        row0 = add(row0, m_odd0);
+        row3 = xor(row3, row0);
+        row3 = rot8(row3);
+        row2 = add(row2, row3);
+        row1 = xor(row1, row2);
+        row1 = rot7(row1);
+
+        row1 = row_rotate_left1(row1);
+        row2 = row_rotate_left2(row2);
+        row3 = row_rotate_left3(row3);
+
+        // Diagonal step: all 4 diagonal quarter-rounds at once.
+        row0 = add(row0, row1);
+        row0 = add(row0, m_even1);
+        row3 = xor(row3, row0);
+        row3 = rot16(row3);
+        row2 = add(row2, row3);
+        row1 = xor(row1, row2);
+        row1 = rot12(row1);
+        row0 = add(row0, row1);
+        row0 = add(row0, m_odd1);
+        row3 = xor(row3, row0);
+        row3 = rot8(row3);
+        row2 = add(row2, row3);
+        row1 = xor(row1, row2);
+        row1 = rot7(row1);
+
+        row1 = row_rotate_left3(row1);
+        row2 = row_rotate_left2(row2);
+        row3 = row_rotate_left1(row3);
+    }
+
+    let low0 = row_to_array(xor(row0, row2));
+    let low1 = row_to_array(xor(row1, row3));
+    let high0 = row_to_array(xor(row2, cv_lo));
+    let high1 = row_to_array(xor(row3, cv_hi));
+
+    let mut out = [0u8; 64];
+    out[0..16].copy_from_slice(&words_to_bytes(low0));
+    out[16..32].copy_from_slice(&words_to_bytes(low1));
+    out[32..48].copy_from_slice(&words_to_bytes(high0));
+    out[48..64].copy_from_slice(&words_to_bytes(high1));
+    out
+}
+
+#[target_feature(enable = "neon")]
+pub unsafe fn hash4(
+    inputs: &[*const u8; DEGREE],
+    key_words: &[u32; 8],
+    offset: u64,
+    offset_deltas: &[u64; 16],
+    flags: u8,
+    flags_start: u8,
+    flags_end: u8,
+    out: &mut [u8; DEGREE * 32],
+) {
+    let mut h_vecs = [
+        set1(key_words[0]),
+        set1(key_words[1]),
+        set1(key_words[2]),
+        set1(key_words[3]),
+        set1(key_words[4]),
+        set1(key_words[5]),
+        set1(key_words[6]),
+        set1(key_words[7]),
+    ];
+
+    let mut counter_low = [0u32; DEGREE];
+    let mut counter_high = [0u32; DEGREE];
+    for i in 0..DEGREE {
+        let chunk_offset = offset + offset_deltas[i];
+        counter_low[i] = chunk_offset as u32;
+        counter_high[i] = (chunk_offset >> 32) as u32;
+    }
+    let counter_low = set4(counter_low[0], counter_low[1], counter_low[2], counter_low[3]);
+    let counter_high = set4(
+        counter_high[0],
+        counter_high[1],
+        counter_high[2],
+        counter_high[3],
+    );
+
+    let blocks = crate::CHUNK_LEN / BLOCK_LEN;
+    let mut block_flags = flags | flags_start;
+    for block in 0..blocks {
+        if block + 1 == blocks {
+            block_flags |= flags_end;
+        }
+        let block_len_vec = set1(BLOCK_LEN as u32);
+        let block_flags_vec = set1(block_flags as u32);
+        let msg_vecs = transpose_msg_vecs(inputs, block * BLOCK_LEN);
+
+        let mut v = [
+            h_vecs[0],
+            h_vecs[1],
+            h_vecs[2],
+            h_vecs[3],
+            h_vecs[4],
+            h_vecs[5],
+            h_vecs[6],
+            h_vecs[7],
+            set1(IV[0]),
+            set1(IV[1]),
+            set1(IV[2]),
+            set1(IV[3]),
+            counter_low,
+            counter_high,
+            block_len_vec,
+            block_flags_vec,
+        ];
+
+        for r in 0..7 {
+            round(&mut v, &msg_vecs, r);
+        }
+
+        h_vecs[0] = xor(v[0], v[8]);
+        h_vecs[1] = xor(v[1], v[9]);
+        h_vecs[2] = xor(v[2], v[10]);
+        h_vecs[3] = xor(v[3], v[11]);
+        h_vecs[4] = xor(v[4], v[12]);
+        h_vecs[5] = xor(v[5], v[13]);
+        h_vecs[6] = xor(v[6], v[14]);
+        h_vecs[7] = xor(v[7], v[15]);
+
+        block_flags = flags;
+    }
+
+    let mut low = [h_vecs[0], h_vecs[1], h_vecs[2], h_vecs[3]];
+    let mut high = [h_vecs[4], h_vecs[5], h_vecs[6], h_vecs[7]];
+    transpose_vecs(&mut low);
+    transpose_vecs(&mut high);
+    for i in 0..4 {
+        storeu(low[i], out.as_mut_ptr().add(i * 32));
+        storeu(high[i], out.as_mut_ptr().add(i * 32 + 16));
+    }
+}
+
+#[target_feature(enable = "neon")]
+pub unsafe fn hash_many<A: arrayvec::Array<Item = u8>>(
+    mut inputs: &[&A],
+    key: &[u8; KEY_LEN],
+    mut offset: u64,
+    offset_deltas: &[u64; 16],
+    flags: u8,
+    flags_start: u8,
+    flags_end: u8,
+    mut out: &mut [u8],
+) {
+    let mut key_words = [0u32; 8];
+    for (word, bytes) in key_words.iter_mut().zip(key.chunks_exact(4)) {
+        *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    }
+
+    while inputs.len() >= DEGREE {
+        let mut fixed_size_inputs: ArrayVec<[*const u8; DEGREE]> = ArrayVec::new();
+        for input in &inputs[..DEGREE] {
+            fixed_size_inputs.push(input.as_ptr());
+        }
+        let fixed_size_inputs = fixed_size_inputs.into_inner().unwrap();
+        let out_block = array_mut_ref4(out);
+        hash4(
+            &fixed_size_inputs,
+            &key_words,
+            offset,
+            offset_deltas,
+            flags,
+            flags_start,
+            flags_end,
+            out_block,
+        );
+        let stride = offset_deltas[1].wrapping_sub(offset_deltas[0]);
+        offset += stride.wrapping_mul(DEGREE as u64);
+        inputs = &inputs[DEGREE..];
+        out = &mut out[DEGREE * 32..];
+    }
+    // Bottom out through portable for the remainder below 4 inputs, the
+    // same way sse41::hash_many falls back on x86.
+    crate::portable::hash_many(
+        inputs,
+        key,
+        offset,
+        offset_deltas,
+        flags,
+        flags_start,
+        flags_end,
+        out,
+    );
+}
+
+#[inline(always)]
+fn array_mut_ref4(out: &mut [u8]) -> &mut [u8; DEGREE * 32] {
+    debug_assert!(out.len() >= DEGREE * 32);
+    unsafe { &mut *(out.as_mut_ptr() as *mut [u8; DEGREE * 32]) }
+}
@@ -1,11 +1,29 @@
 use crate::{portable, Flags, BLOCK_LEN, KEY_LEN};
 
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-use crate::{avx2, sse41};
+use crate::{avx2, avx512, sse41};
+
+#[cfg(target_arch = "aarch64")]
+use crate::neon;
+
+#[cfg(feature = "portable-simd")]
+use crate::simd128;
 
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-pub const MAX_SIMD_DEGREE: usize = 8;
-#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+pub const MAX_SIMD_DEGREE: usize = 16;
+#[cfg(target_arch = "aarch64")]
+pub const MAX_SIMD_DEGREE: usize = 4;
+// On everything else, detect() falls back to Simd128 (degree 4) when the
+// portable-simd feature is enabled, and to Portable (degree 1) otherwise.
+#[cfg(all(
+    not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")),
+    feature = "portable-simd"
+))]
+pub const MAX_SIMD_DEGREE: usize = 4;
+#[cfg(all(
+    not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")),
+    not(feature = "portable-simd")
+))]
 pub const MAX_SIMD_DEGREE: usize = 1;
 
 #[derive(Clone, Copy, Debug)]
@@ -15,12 +33,24 @@ pub enum Platform {
     SSE41,
     #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
     AVX2,
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    AVX512,
+    #[cfg(target_arch = "aarch64")]
+    NEON,
+    // A middle tier below the arch-specific backends above: no hand-written
+    // intrinsics, but still faster than scalar portable on targets (WASM,
+    // RISC-V, ...) that lower core::simd's 128-bit lanes well.
+    #[cfg(feature = "portable-simd")]
+    Simd128,
 }
 
 impl Platform {
     pub fn detect() -> Self {
         #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
         {
+            if avx512_detected() {
+                return Platform::AVX512;
+            }
             if avx2_detected() {
                 return Platform::AVX2;
             }
@@ -28,9 +58,90 @@ impl Platform {
                 return Platform::SSE41;
             }
         }
+        #[cfg(target_arch = "aarch64")]
+        {
+            if neon_detected() {
+                return Platform::NEON;
+            }
+        }
+        #[cfg(feature = "portable-simd")]
+        {
+            return Platform::Simd128;
+        }
+        #[allow(unreachable_code)]
+        Platform::Portable
+    }
+
+    // The methods below let callers pin a specific backend instead of
+    // taking whatever detect() picks, so the test suite can cross-check
+    // every backend the host supports and so benchmarks can compare them
+    // head-to-head. This mirrors the Implementation::portable() /
+    // Implementation::sse41() constructors in blake2b_simd.
+
+    pub fn portable() -> Self {
         Platform::Portable
     }
 
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub fn sse41() -> Option<Self> {
+        if sse41_detected() {
+            Some(Platform::SSE41)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub fn avx2() -> Option<Self> {
+        if avx2_detected() {
+            Some(Platform::AVX2)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub fn avx512() -> Option<Self> {
+        if avx512_detected() {
+            Some(Platform::AVX512)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    pub fn neon() -> Option<Self> {
+        if neon_detected() {
+            Some(Platform::NEON)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "portable-simd")]
+    pub fn simd128() -> Option<Self> {
+        Some(Platform::Simd128)
+    }
+
+    /// Validate that `platform` is actually supported by the running CPU,
+    /// returning `None` if it isn't. `Platform::Portable` is always
+    /// supported.
+    pub fn try_new(platform: Platform) -> Option<Self> {
+        match platform {
+            Platform::Portable => Some(Platform::Portable),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Platform::SSE41 => Self::sse41(),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Platform::AVX2 => Self::avx2(),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Platform::AVX512 => Self::avx512(),
+            #[cfg(target_arch = "aarch64")]
+            Platform::NEON => Self::neon(),
+            #[cfg(feature = "portable-simd")]
+            Platform::Simd128 => Self::simd128(),
+        }
+    }
+
     pub fn simd_degree(&self) -> usize {
         let degree = match self {
             Platform::Portable => 1,
@@ -38,6 +149,12 @@ impl Platform {
             Platform::SSE41 => 4,
             #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
             Platform::AVX2 => 8,
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Platform::AVX512 => 16,
+            #[cfg(target_arch = "aarch64")]
+            Platform::NEON => 4,
+            #[cfg(feature = "portable-simd")]
+            Platform::Simd128 => 4,
         };
         debug_assert!(degree <= MAX_SIMD_DEGREE);
         degree
@@ -58,6 +175,20 @@ impl Platform {
             Platform::SSE41 | Platform::AVX2 => unsafe {
                 sse41::compress(cv, block, block_len, offset, flags.bits())
             },
+            // Safe because detect() checked for platform support.
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Platform::AVX512 => unsafe {
+                avx512::compress(cv, block, block_len, offset, flags.bits())
+            },
+            // Safe because detect() checked for platform support.
+            #[cfg(target_arch = "aarch64")]
+            Platform::NEON => unsafe {
+                neon::compress(cv, block, block_len, offset, flags.bits())
+            },
+            #[cfg(feature = "portable-simd")]
+            Platform::Simd128 => {
+                simd128::compress(cv, block, block_len, offset, flags.bits())
+            }
         }
     }
 
@@ -121,8 +252,84 @@ impl Platform {
                     out,
                 )
             },
+            // Safe because detect() checked for platform support.
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Platform::AVX512 => unsafe {
+                avx512::hash_many(
+                    inputs,
+                    key,
+                    offset,
+                    offset_deltas,
+                    flags.bits(),
+                    flags_start.bits(),
+                    flags_end.bits(),
+                    out,
+                )
+            },
+            // Safe because detect() checked for platform support.
+            #[cfg(target_arch = "aarch64")]
+            Platform::NEON => unsafe {
+                neon::hash_many(
+                    inputs,
+                    key,
+                    offset,
+                    offset_deltas,
+                    flags.bits(),
+                    flags_start.bits(),
+                    flags_end.bits(),
+                    out,
+                )
+            },
+            #[cfg(feature = "portable-simd")]
+            Platform::Simd128 => simd128::hash_many(
+                inputs,
+                key,
+                offset,
+                offset_deltas,
+                flags.bits(),
+                flags_start.bits(),
+                flags_end.bits(),
+                out,
+            ),
+        }
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+#[inline(always)]
+pub fn neon_detected() -> bool {
+    // Static check, e.g. for building with target-cpu=native. NEON is
+    // effectively baseline on aarch64, so this is true almost everywhere.
+    #[cfg(target_feature = "neon")]
+    {
+        return true;
+    }
+    // Dyanmic check, if std is enabled.
+    #[cfg(feature = "std")]
+    {
+        if std::arch::is_aarch64_feature_detected!("neon") {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[inline(always)]
+pub fn avx512_detected() -> bool {
+    // Static check, e.g. for building with target-cpu=native.
+    #[cfg(all(target_feature = "avx512f", target_feature = "avx512vl"))]
+    {
+        return true;
+    }
+    // Dyanmic check, if std is enabled.
+    #[cfg(feature = "std")]
+    {
+        if is_x86_feature_detected!("avx512f") && is_x86_feature_detected!("avx512vl") {
+            return true;
         }
     }
+    false
 }
 
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
@@ -159,4 +366,108 @@ pub fn sse41_detected() -> bool {
         }
     }
     false
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CHUNK_LEN, KEY_LEN, OUT_LEN};
+
+    // Every backend the host supports gets cross-checked against the
+    // portable implementation here, for both the single-block compress()
+    // path and the many-chunk hash_many() path. This is the matrix the
+    // force-backend constructors above exist to make possible.
+    fn other_platforms() -> Vec<Platform> {
+        let mut platforms = Vec::new();
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            platforms.extend(Platform::sse41());
+            platforms.extend(Platform::avx2());
+            platforms.extend(Platform::avx512());
+        }
+        #[cfg(target_arch = "aarch64")]
+        {
+            platforms.extend(Platform::neon());
+        }
+        #[cfg(feature = "portable-simd")]
+        {
+            platforms.extend(Platform::simd128());
+        }
+        platforms
+    }
+
+    #[test]
+    fn test_compress_vs_portable() {
+        let mut cv = [0u8; 32];
+        for (i, b) in cv.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        let mut block = [0u8; BLOCK_LEN];
+        for (i, b) in block.iter_mut().enumerate() {
+            *b = (i as u8).wrapping_mul(7);
+        }
+        let expected =
+            Platform::Portable.compress(&cv, &block, BLOCK_LEN as u8, 0, Flags::CHUNK_START);
+        for platform in other_platforms() {
+            let out = platform.compress(&cv, &block, BLOCK_LEN as u8, 0, Flags::CHUNK_START);
+            assert_eq!(
+                expected, out,
+                "{:?} disagreed with Portable in compress()",
+                platform
+            );
+        }
+    }
+
+    #[test]
+    fn test_hash_many_vs_portable() {
+        // Enough inputs to exercise a full group on every backend
+        // (up to MAX_SIMD_DEGREE == 16) plus a ragged remainder below it.
+        const NUM_INPUTS: usize = 2 * 16 + 3;
+        let key = [99u8; KEY_LEN];
+        let mut offset_deltas = [0u64; 16];
+        for (i, delta) in offset_deltas.iter_mut().enumerate() {
+            *delta = i as u64 * CHUNK_LEN as u64;
+        }
+        let inputs: Vec<[u8; CHUNK_LEN]> = (0..NUM_INPUTS)
+            .map(|i| {
+                let mut input = [0u8; CHUNK_LEN];
+                for (j, b) in input.iter_mut().enumerate() {
+                    *b = (i as u8).wrapping_add(j as u8);
+                }
+                input
+            })
+            .collect();
+        let input_refs: Vec<&[u8; CHUNK_LEN]> = inputs.iter().collect();
+
+        let mut expected = vec![0u8; NUM_INPUTS * OUT_LEN];
+        Platform::Portable.hash_many(
+            &input_refs,
+            &key,
+            0,
+            &offset_deltas,
+            Flags::empty(),
+            Flags::CHUNK_START,
+            Flags::CHUNK_END,
+            &mut expected,
+        );
+
+        for platform in other_platforms() {
+            let mut out = vec![0u8; NUM_INPUTS * OUT_LEN];
+            platform.hash_many(
+                &input_refs,
+                &key,
+                0,
+                &offset_deltas,
+                Flags::empty(),
+                Flags::CHUNK_START,
+                Flags::CHUNK_END,
+                &mut out,
+            );
+            assert_eq!(
+                expected, out,
+                "{:?} disagreed with Portable in hash_many()",
+                platform
+            );
+        }
+    }
 }
\ No newline at end of file
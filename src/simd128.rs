@@ -0,0 +1,396 @@
+//! A portable SIMD backend built on `core::simd`, for targets (WASM,
+//! RISC-V, ...) that have no hand-written intrinsics backend of their own
+//! but still lower 128-bit vector types well. This requires the
+//! `portable-simd` crate feature, which in turn requires a nightly
+//! compiler with `#![feature(portable_simd)]` enabled at the crate root.
+
+use crate::{BLOCK_LEN, IV, KEY_LEN, MSG_SCHEDULE};
+use arrayvec::ArrayVec;
+use core::simd::u32x4;
+
+pub const DEGREE: usize = 4;
+
+// core::simd only implements Shr/Shl for Simd-by-Simd, so the shift amount
+// has to be splatted into a vector rather than passed as a bare scalar.
+#[inline(always)]
+fn rot16(a: u32x4) -> u32x4 {
+    (a >> u32x4::splat(16)) | (a << u32x4::splat(16))
+}
+
+#[inline(always)]
+fn rot12(a: u32x4) -> u32x4 {
+    (a >> u32x4::splat(12)) | (a << u32x4::splat(20))
+}
+
+#[inline(always)]
+fn rot8(a: u32x4) -> u32x4 {
+    (a >> u32x4::splat(8)) | (a << u32x4::splat(24))
+}
+
+#[inline(always)]
+fn rot7(a: u32x4) -> u32x4 {
+    (a >> u32x4::splat(7)) | (a << u32x4::splat(25))
+}
+
+#[inline(always)]
+fn g(
+    v: &mut [u32x4; 16],
+    m: &[u32x4; 16],
+    a: usize,
+    b: usize,
+    c: usize,
+    d: usize,
+    x: usize,
+    y: usize,
+) {
+    v[a] += m[x];
+    v[a] += v[b];
+    v[d] ^= v[a];
+    v[d] = rot16(v[d]);
+    v[c] += v[d];
+    v[b] ^= v[c];
+    v[b] = rot12(v[b]);
+    v[a] += m[y];
+    v[a] += v[b];
+    v[d] ^= v[a];
+    v[d] = rot8(v[d]);
+    v[c] += v[d];
+    v[b] ^= v[c];
+    v[b] = rot7(v[b]);
+}
+
+#[inline(always)]
+fn round(v: &mut [u32x4; 16], m: &[u32x4; 16], round_idx: usize) {
+    let s = &MSG_SCHEDULE[round_idx];
+    g(v, m, 0, 4, 8, 12, s[0] as usize, s[1] as usize);
+    g(v, m, 1, 5, 9, 13, s[2] as usize, s[3] as usize);
+    g(v, m, 2, 6, 10, 14, s[4] as usize, s[5] as usize);
+    g(v, m, 3, 7, 11, 15, s[6] as usize, s[7] as usize);
+    g(v, m, 0, 5, 10, 15, s[8] as usize, s[9] as usize);
+    g(v, m, 1, 6, 11, 12, s[10] as usize, s[11] as usize);
+    g(v, m, 2, 7, 8, 13, s[12] as usize, s[13] as usize);
+    g(v, m, 3, 4, 9, 14, s[14] as usize, s[15] as usize);
+}
+
+// Transpose the 4 state vectors (one per chunk), the same structure the
+// NEON and SSE4.1 degree-4 backends transpose, expressed with lane
+// shuffles instead of arch intrinsics.
+#[inline(always)]
+fn transpose_vecs(vecs: &mut [u32x4; DEGREE]) {
+    let [a, b, c, d] = *vecs;
+    let a_arr = a.to_array();
+    let b_arr = b.to_array();
+    let c_arr = c.to_array();
+    let d_arr = d.to_array();
+    vecs[0] = u32x4::from_array([a_arr[0], b_arr[0], c_arr[0], d_arr[0]]);
+    vecs[1] = u32x4::from_array([a_arr[1], b_arr[1], c_arr[1], d_arr[1]]);
+    vecs[2] = u32x4::from_array([a_arr[2], b_arr[2], c_arr[2], d_arr[2]]);
+    vecs[3] = u32x4::from_array([a_arr[3], b_arr[3], c_arr[3], d_arr[3]]);
+}
+
+#[inline(always)]
+fn load_word(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+#[inline(always)]
+unsafe fn transpose_msg_vecs(inputs: &[*const u8; DEGREE], block_offset: usize) -> [u32x4; 16] {
+    let mut out = [u32x4::splat(0); 16];
+    for word in 0..16 {
+        let word_offset = block_offset + word * 4;
+        let mut lanes = [0u32; DEGREE];
+        for (lane, input) in inputs.iter().enumerate() {
+            let bytes = core::slice::from_raw_parts(input.add(word_offset), 4);
+            lanes[lane] = load_word(bytes);
+        }
+        out[word] = u32x4::from_array(lanes);
+    }
+    out
+}
+
+// A single compress() call has no chunks to parallelize across, so unlike
+// hash4 below, the 16 state words are packed 4-to-a-lane-vector as "rows"
+// (row0 = words 0-3, row1 = words 4-7, row2 = words 8-11, row3 = words
+// 12-15) and the G function runs on all 4 columns (then all 4 diagonals)
+// of a row at once, the same technique the arch-specific backends use for
+// their own compress().
+#[inline(always)]
+fn row_rotate_left1(a: u32x4) -> u32x4 {
+    let w = a.to_array();
+    u32x4::from_array([w[1], w[2], w[3], w[0]])
+}
+
+#[inline(always)]
+fn row_rotate_left2(a: u32x4) -> u32x4 {
+    let w = a.to_array();
+    u32x4::from_array([w[2], w[3], w[0], w[1]])
+}
+
+#[inline(always)]
+fn row_rotate_left3(a: u32x4) -> u32x4 {
+    let w = a.to_array();
+    u32x4::from_array([w[3], w[0], w[1], w[2]])
+}
+
+pub fn compress(
+    cv: &[u8; 32],
+    block: &[u8; BLOCK_LEN],
+    block_len: u8,
+    offset: u64,
+    flags: u8,
+) -> [u8; 64] {
+    let mut cv_words = [0u32; 8];
+    for (word, bytes) in cv_words.iter_mut().zip(cv.chunks_exact(4)) {
+        *word = load_word(bytes);
+    }
+    let mut block_words = [0u32; 16];
+    for (word, bytes) in block_words.iter_mut().zip(block.chunks_exact(4)) {
+        *word = load_word(bytes);
+    }
+
+    let cv_lo = u32x4::from_array([cv_words[0], cv_words[1], cv_words[2], cv_words[3]]);
+    let cv_hi = u32x4::from_array([cv_words[4], cv_words[5], cv_words[6], cv_words[7]]);
+    let mut row0 = cv_lo;
+    let mut row1 = cv_hi;
+    let mut row2 = u32x4::from_array([IV[0], IV[1], IV[2], IV[3]]);
+    let mut row3 = u32x4::from_array([
+        offset as u32,
+        (offset >> 32) as u32,
+        block_len as u32,
+        flags as u32,
+    ]);
+
+    for round_idx in 0..7 {
+        let s = &MSG_SCHEDULE[round_idx];
+        let m_even0 = u32x4::from_array([
+            block_words[s[0] as usize],
+            block_words[s[2] as usize],
+            block_words[s[4] as usize],
+            block_words[s[6] as usize],
+        ]);
+        let m_odd0 = u32x4::from_array([
+            block_words[s[1] as usize],
+            block_words[s[3] as usize],
+            block_words[s[5] as usize],
+            block_words[s[7] as usize],
+        ]);
+        let m_even1 = u32x4::from_array([
+            block_words[s[8] as usize],
+            block_words[s[10] as usize],
+            block_words[s[12] as usize],
+            block_words[s[14] as usize],
+        ]);
+        let m_odd1 = u32x4::from_array([
+            block_words[s[9] as usize],
+            block_words[s[11] as usize],
+            block_words[s[13] as usize],
+            block_words[s[15] as usize],
+        ]);
+
+        // Column step: all 4 column quarter-rounds at once, one per lane.
+        row0 += row1;
+        row0 += m_even0;
+        row3 ^= row0;
+        row3 = rot16(row3);
+        row2 += row3;
+        row1 ^= row2;
+        row1 = rot12(row1);
+        row0 += row1;
+        row0 += m_odd0;
+        row3 ^= row0;
+        row3 = rot8(row3);
+        row2 += row3;
+        row1 ^= row2;
+        row1 = rot7(row1);
+
+        row1 = row_rotate_left1(row1);
+        row2 = row_rotate_left2(row2);
+        row3 = row_rotate_left3(row3);
+
+        // Diagonal step: all 4 diagonal quarter-rounds at once.
+        row0 += row1;
+        row0 += m_even1;
+        row3 ^= row0;
+        row3 = rot16(row3);
+        row2 += row3;
+        row1 ^= row2;
+        row1 = rot12(row1);
+        row0 += row1;
+        row0 += m_odd1;
+        row3 ^= row0;
+        row3 = rot8(row3);
+        row2 += row3;
+        row1 ^= row2;
+        row1 = rot7(row1);
+
+        row1 = row_rotate_left3(row1);
+        row2 = row_rotate_left2(row2);
+        row3 = row_rotate_left1(row3);
+    }
+
+    let low0 = (row0 ^ row2).to_array();
+    let low1 = (row1 ^ row3).to_array();
+    let high0 = (row2 ^ cv_lo).to_array();
+    let high1 = (row3 ^ cv_hi).to_array();
+
+    let mut out = [0u8; 64];
+    out[0..16].copy_from_slice(&u32_array_to_bytes(low0));
+    out[16..32].copy_from_slice(&u32_array_to_bytes(low1));
+    out[32..48].copy_from_slice(&u32_array_to_bytes(high0));
+    out[48..64].copy_from_slice(&u32_array_to_bytes(high1));
+    out
+}
+
+pub fn hash4(
+    inputs: &[*const u8; DEGREE],
+    key_words: &[u32; 8],
+    offset: u64,
+    offset_deltas: &[u64; 16],
+    flags: u8,
+    flags_start: u8,
+    flags_end: u8,
+    out: &mut [u8; DEGREE * 32],
+) {
+    let mut h_vecs = [
+        u32x4::splat(key_words[0]),
+        u32x4::splat(key_words[1]),
+        u32x4::splat(key_words[2]),
+        u32x4::splat(key_words[3]),
+        u32x4::splat(key_words[4]),
+        u32x4::splat(key_words[5]),
+        u32x4::splat(key_words[6]),
+        u32x4::splat(key_words[7]),
+    ];
+
+    let mut counter_low = [0u32; DEGREE];
+    let mut counter_high = [0u32; DEGREE];
+    for i in 0..DEGREE {
+        let chunk_offset = offset + offset_deltas[i];
+        counter_low[i] = chunk_offset as u32;
+        counter_high[i] = (chunk_offset >> 32) as u32;
+    }
+    let counter_low = u32x4::from_array(counter_low);
+    let counter_high = u32x4::from_array(counter_high);
+
+    let blocks = crate::CHUNK_LEN / BLOCK_LEN;
+    let mut block_flags = flags | flags_start;
+    for block in 0..blocks {
+        if block + 1 == blocks {
+            block_flags |= flags_end;
+        }
+        let block_len_vec = u32x4::splat(BLOCK_LEN as u32);
+        let block_flags_vec = u32x4::splat(block_flags as u32);
+        // Safe because the caller guarantees each input points at a chunk
+        // with at least CHUNK_LEN bytes remaining.
+        let msg_vecs = unsafe { transpose_msg_vecs(inputs, block * BLOCK_LEN) };
+
+        let mut v = [
+            h_vecs[0],
+            h_vecs[1],
+            h_vecs[2],
+            h_vecs[3],
+            h_vecs[4],
+            h_vecs[5],
+            h_vecs[6],
+            h_vecs[7],
+            u32x4::splat(IV[0]),
+            u32x4::splat(IV[1]),
+            u32x4::splat(IV[2]),
+            u32x4::splat(IV[3]),
+            counter_low,
+            counter_high,
+            block_len_vec,
+            block_flags_vec,
+        ];
+
+        for r in 0..7 {
+            round(&mut v, &msg_vecs, r);
+        }
+
+        h_vecs[0] = v[0] ^ v[8];
+        h_vecs[1] = v[1] ^ v[9];
+        h_vecs[2] = v[2] ^ v[10];
+        h_vecs[3] = v[3] ^ v[11];
+        h_vecs[4] = v[4] ^ v[12];
+        h_vecs[5] = v[5] ^ v[13];
+        h_vecs[6] = v[6] ^ v[14];
+        h_vecs[7] = v[7] ^ v[15];
+
+        block_flags = flags;
+    }
+
+    let mut low = [h_vecs[0], h_vecs[1], h_vecs[2], h_vecs[3]];
+    let mut high = [h_vecs[4], h_vecs[5], h_vecs[6], h_vecs[7]];
+    transpose_vecs(&mut low);
+    transpose_vecs(&mut high);
+    for i in 0..4 {
+        out[i * 32..i * 32 + 16].copy_from_slice(&u32_array_to_bytes(low[i].to_array()));
+        out[i * 32 + 16..i * 32 + 32].copy_from_slice(&u32_array_to_bytes(high[i].to_array()));
+    }
+}
+
+#[inline(always)]
+fn u32_array_to_bytes(words: [u32; 4]) -> [u8; 16] {
+    let mut bytes = [0u8; 16];
+    for (chunk, word) in bytes.chunks_exact_mut(4).zip(words.iter()) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    bytes
+}
+
+pub fn hash_many<A: arrayvec::Array<Item = u8>>(
+    mut inputs: &[&A],
+    key: &[u8; KEY_LEN],
+    mut offset: u64,
+    offset_deltas: &[u64; 16],
+    flags: u8,
+    flags_start: u8,
+    flags_end: u8,
+    mut out: &mut [u8],
+) {
+    let mut key_words = [0u32; 8];
+    for (word, bytes) in key_words.iter_mut().zip(key.chunks_exact(4)) {
+        *word = load_word(bytes);
+    }
+
+    while inputs.len() >= DEGREE {
+        let mut fixed_size_inputs: ArrayVec<[*const u8; DEGREE]> = ArrayVec::new();
+        for input in &inputs[..DEGREE] {
+            fixed_size_inputs.push(input.as_ptr());
+        }
+        let fixed_size_inputs = fixed_size_inputs.into_inner().unwrap();
+        let out_block = array_mut_ref4(out);
+        hash4(
+            &fixed_size_inputs,
+            &key_words,
+            offset,
+            offset_deltas,
+            flags,
+            flags_start,
+            flags_end,
+            out_block,
+        );
+        let stride = offset_deltas[1].wrapping_sub(offset_deltas[0]);
+        offset += stride.wrapping_mul(DEGREE as u64);
+        inputs = &inputs[DEGREE..];
+        out = &mut out[DEGREE * 32..];
+    }
+    // Bottom out through the scalar portable path for the remainder below
+    // 4 inputs, the same as the NEON backend's hash_many.
+    crate::portable::hash_many(
+        inputs,
+        key,
+        offset,
+        offset_deltas,
+        flags,
+        flags_start,
+        flags_end,
+        out,
+    );
+}
+
+#[inline(always)]
+fn array_mut_ref4(out: &mut [u8]) -> &mut [u8; DEGREE * 32] {
+    debug_assert!(out.len() >= DEGREE * 32);
+    unsafe { &mut *(out.as_mut_ptr() as *mut [u8; DEGREE * 32]) }
+}